@@ -0,0 +1,93 @@
+//! Rewriting relative, non-markdown link/image targets so embedded images
+//! and attachments resolve against the `/raw/` asset route.
+
+use pulldown_cmark::{Event, Tag};
+use std::path::Path;
+
+/// Rewrite image and link destinations in a parsed event stream, leaving
+/// absolute URLs, fragments, and `.md` links untouched.
+pub fn rewrite_asset_links<'a>(events: Vec<Event<'a>>, current: &str) -> Vec<Event<'a>> {
+    events
+        .into_iter()
+        .map(|event| match event {
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => Event::Start(Tag::Image {
+                link_type,
+                dest_url: rewrite_target(&dest_url, current).into(),
+                title,
+                id,
+            }),
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => Event::Start(Tag::Link {
+                link_type,
+                dest_url: rewrite_target(&dest_url, current).into(),
+                title,
+                id,
+            }),
+            other => other,
+        })
+        .collect()
+}
+
+fn rewrite_target(target: &str, current: &str) -> String {
+    let is_remote = target.contains("://") || target.starts_with('#') || target.starts_with("mailto:");
+    let is_root_relative = target.starts_with('/');
+    let is_markdown = target.ends_with(".md");
+    if is_remote || is_root_relative || is_markdown {
+        return target.to_string();
+    }
+    format!("/raw/{}", resolve_relative(current, target))
+}
+
+/// Resolve `target` against the directory `current` (a file key) lives in,
+/// collapsing `.` and `..` segments.
+fn resolve_relative(current: &str, target: &str) -> String {
+    let base = Path::new(current).parent().unwrap_or(Path::new(""));
+    let mut parts: Vec<&str> = base
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect();
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(segment),
+        }
+    }
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_relative_sibling() {
+        assert_eq!(resolve_relative("notes/todo.md", "image.png"), "notes/image.png");
+    }
+
+    #[test]
+    fn resolve_relative_parent_dir() {
+        assert_eq!(resolve_relative("notes/todo.md", "../shared/logo.png"), "shared/logo.png");
+    }
+
+    #[test]
+    fn resolve_relative_top_level_current() {
+        assert_eq!(resolve_relative("index.md", "logo.png"), "logo.png");
+    }
+
+    #[test]
+    fn resolve_relative_collapses_dot_segments() {
+        assert_eq!(resolve_relative("notes/todo.md", "./sub/./img.png"), "notes/sub/img.png");
+    }
+}
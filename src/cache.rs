@@ -0,0 +1,114 @@
+//! In-memory caches so repeated requests don't re-walk the tree or
+//! re-render markdown that hasn't changed on disk.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+struct CachedPage {
+    mtime: SystemTime,
+    html: String,
+}
+
+/// Caches rendered page HTML per file, invalidated when the file's mtime
+/// differs from what was last seen.
+pub struct PageCache {
+    pages: Mutex<HashMap<String, CachedPage>>,
+}
+
+impl PageCache {
+    pub fn new() -> Self {
+        Self {
+            pages: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the cached HTML for `path` if its stored mtime still matches,
+    /// otherwise run `render` and cache the result under `mtime`. The lock
+    /// is only held to read and to insert, not across `render` itself, so a
+    /// cold render of one page doesn't block a cache hit on another.
+    pub fn get_or_render(
+        &self,
+        path: &str,
+        mtime: SystemTime,
+        render: impl FnOnce() -> String,
+    ) -> String {
+        {
+            let pages = self.pages.lock().unwrap();
+            if let Some(cached) = pages.get(path) {
+                if cached.mtime == mtime {
+                    return cached.html.clone();
+                }
+            }
+        }
+        let html = render();
+        let mut pages = self.pages.lock().unwrap();
+        pages.insert(
+            path.to_string(),
+            CachedPage {
+                mtime,
+                html: html.clone(),
+            },
+        );
+        html
+    }
+
+    /// Drop a cached entry, forcing the next request to re-render it.
+    pub fn invalidate(&self, path: &str) {
+        self.pages.lock().unwrap().remove(path);
+    }
+
+    /// Drop every cached entry. A page's rendered HTML also depends on
+    /// which other files exist (wikilink resolution, backlinks), so adding
+    /// or removing a file can change it without touching its own mtime.
+    pub fn invalidate_all(&self) {
+        self.pages.lock().unwrap().clear();
+    }
+}
+
+const DIR_LISTING_TTL: Duration = Duration::from_secs(2);
+
+type Backlinks = BTreeMap<String, Vec<String>>;
+
+/// Caches the recursive markdown file listing, and the backlink index built
+/// from it, for a short TTL. Both share the same lifetime (they're only
+/// stale when a file is added, removed, or its links change) so a full
+/// `read_dir` walk and corpus-wide backlink scan aren't repeated on every
+/// page load.
+pub struct DirCache {
+    entry: Mutex<Option<(Instant, BTreeMap<String, String>, Backlinks)>>,
+}
+
+impl DirCache {
+    pub fn new() -> Self {
+        Self {
+            entry: Mutex::new(None),
+        }
+    }
+
+    /// Lock is only held to read and to insert, not across `collect` itself
+    /// (a full tree walk plus corpus-wide backlink scan), so a cache hit
+    /// doesn't queue up behind a concurrent, slower re-collection.
+    pub fn get_or_collect(
+        &self,
+        collect: impl FnOnce() -> (BTreeMap<String, String>, Backlinks),
+    ) -> (BTreeMap<String, String>, Backlinks) {
+        {
+            let entry = self.entry.lock().unwrap();
+            if let Some((fetched_at, files, backlinks)) = entry.as_ref() {
+                if fetched_at.elapsed() < DIR_LISTING_TTL {
+                    return (files.clone(), backlinks.clone());
+                }
+            }
+        }
+        let (files, backlinks) = collect();
+        let mut entry = self.entry.lock().unwrap();
+        *entry = Some((Instant::now(), files.clone(), backlinks.clone()));
+        (files, backlinks)
+    }
+
+    /// Force the next call to `get_or_collect` to re-walk the tree.
+    pub fn invalidate(&self) {
+        *self.entry.lock().unwrap() = None;
+    }
+}
@@ -0,0 +1,192 @@
+//! Recursive markdown file discovery, with dotfile/glob filtering and
+//! draft front matter exclusion.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub fn collect_md_files(root: &PathBuf, hidden: &[String]) -> BTreeMap<String, String> {
+    let mut files = BTreeMap::new();
+    collect_recursive(root, root, hidden, &mut files);
+    files
+}
+
+fn collect_recursive(
+    root: &PathBuf,
+    dir: &PathBuf,
+    hidden: &[String],
+    files: &mut BTreeMap<String, String>,
+) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if is_hidden(name, hidden) {
+            continue;
+        }
+        if path.is_dir() {
+            collect_recursive(root, &path, hidden, files);
+        } else if path.extension().is_some_and(|e| e == "md") {
+            if is_draft(&path) {
+                continue;
+            }
+            let relative = path.strip_prefix(root).unwrap();
+            let key = relative.to_string_lossy().to_string();
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            files.insert(key, name);
+        }
+    }
+}
+
+/// Skip dot-directories/dot-files by default, plus anything matching a
+/// user-supplied `--hidden` name or glob (`*` wildcard only).
+fn is_hidden(name: &str, hidden: &[String]) -> bool {
+    name.starts_with('.') || hidden.iter().any(|pattern| matches_glob(pattern, name))
+}
+
+fn matches_glob(pattern: &str, name: &str) -> bool {
+    let Some((prefix, mut rest)) = pattern.split_once('*') else {
+        return pattern == name;
+    };
+    let Some(mut name) = name.strip_prefix(prefix) else {
+        return false;
+    };
+    loop {
+        match rest.split_once('*') {
+            Some((part, tail)) => {
+                let Some(idx) = name.find(part) else {
+                    return false;
+                };
+                name = &name[idx + part.len()..];
+                rest = tail;
+            }
+            None => return name.ends_with(rest),
+        }
+    }
+}
+
+/// Split a leading `---`-delimited front matter block (if any) off of
+/// `content`, returning `(front_matter, body)`. The closing fence must be a
+/// whole line (a `---` immediately followed by a newline or end of file) —
+/// anything else (a `---` that's part of a front-matter value, say) means
+/// there's no valid closing fence, and `content` is treated as having no
+/// front matter at all rather than risking an empty body.
+fn split_front_matter(content: &str) -> (Option<&str>, &str) {
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return (None, content);
+    };
+    let mut search_from = 0;
+    loop {
+        let Some(found) = rest[search_from..].find("\n---") else {
+            return (None, content);
+        };
+        let idx = search_from + found;
+        let after = idx + "\n---".len();
+        match rest[after..].strip_prefix('\n') {
+            Some(body) => return (Some(&rest[..idx]), body),
+            None if after == rest.len() => return (Some(&rest[..idx]), ""),
+            None => search_from = after,
+        }
+    }
+}
+
+/// Strip a leading front matter block, if present, so it isn't rendered as
+/// visible markdown.
+pub fn strip_front_matter(content: &str) -> &str {
+    split_front_matter(content).1
+}
+
+/// Check a minimal `---`-delimited front matter block for `draft: true` or
+/// `published: false`.
+fn is_draft(path: &Path) -> bool {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    let Some(front) = split_front_matter(&content).0 else {
+        return false;
+    };
+    front.lines().any(|line| {
+        let Some((key, value)) = line.split_once(':') else {
+            return false;
+        };
+        matches!(
+            (key.trim(), value.trim()),
+            ("draft", "true") | ("published", "false")
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_glob_exact() {
+        assert!(matches_glob("notes.md", "notes.md"));
+        assert!(!matches_glob("notes.md", "other.md"));
+    }
+
+    #[test]
+    fn matches_glob_wildcard() {
+        assert!(matches_glob("*.tmp", "scratch.tmp"));
+        assert!(matches_glob("draft-*", "draft-one"));
+        assert!(matches_glob("a*b*c", "aXbYc"));
+        assert!(!matches_glob("a*b*c", "aXbY"));
+        assert!(!matches_glob("*.tmp", "scratch.md"));
+    }
+
+    #[test]
+    fn split_front_matter_present() {
+        let content = "---\ntitle: Hi\n---\nbody text";
+        let (front, body) = split_front_matter(content);
+        assert_eq!(front, Some("title: Hi"));
+        assert_eq!(body, "body text");
+    }
+
+    #[test]
+    fn split_front_matter_absent() {
+        let content = "# just a heading\n";
+        let (front, body) = split_front_matter(content);
+        assert_eq!(front, None);
+        assert_eq!(body, content);
+    }
+
+    #[test]
+    fn split_front_matter_no_whole_line_closing_fence() {
+        // The only `\n---` here is part of a front-matter value, not a
+        // closing fence on its own line, so the whole thing is treated as
+        // having no front matter rather than swallowing the body.
+        let content = "---\nsummary: a\n---b\nnot a fence\n";
+        let (front, body) = split_front_matter(content);
+        assert_eq!(front, None);
+        assert_eq!(body, content);
+    }
+
+    #[test]
+    fn split_front_matter_closing_fence_at_eof() {
+        let content = "---\ntitle: Hi\n---";
+        let (front, body) = split_front_matter(content);
+        assert_eq!(front, Some("title: Hi"));
+        assert_eq!(body, "");
+    }
+
+    #[test]
+    fn is_draft_detects_flag() {
+        let dir = std::env::temp_dir().join(format!("displaymd-test-{:?}", std::thread::current().id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let draft_path = dir.join("draft.md");
+        std::fs::write(&draft_path, "---\ndraft: true\n---\nbody").unwrap();
+        assert!(is_draft(&draft_path));
+
+        let published_path = dir.join("published.md");
+        std::fs::write(&published_path, "---\ntitle: Hi\n---\nbody").unwrap();
+        assert!(!is_draft(&published_path));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,153 @@
+//! Static-site export: pre-render every markdown file to a self-contained
+//! HTML file under an output directory, reusing the same page-building code
+//! path as the live `view` handler so server and export output stay in sync.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub fn run(root: &PathBuf, outdir: &Path, gfm: bool, home: &str, hidden: &[String]) -> io::Result<()> {
+    let files = crate::collect::collect_md_files(root, hidden);
+    let backlinks = crate::links::build_backlinks(root, &files);
+
+    std::fs::create_dir_all(outdir)?;
+    std::fs::write(outdir.join("style.css"), crate::CSS)?;
+
+    for key in files.keys() {
+        let raw = std::fs::read_to_string(root.join(key))?;
+        let body = crate::collect::strip_front_matter(&raw);
+        let linked = crate::links::render_wikilinks(body, &files);
+        let content = crate::render_markdown(&linked, gfm, key);
+        let sidebar = crate::sidebar::build_sidebar(&files, key);
+        let linked_from = crate::build_backlinks_section(backlinks.get(key));
+        let depth = Path::new(key)
+            .parent()
+            .map(|p| p.components().count())
+            .unwrap_or(0);
+        let style_tag = format!(
+            r#"<link rel="stylesheet" href="{}style.css">"#,
+            "../".repeat(depth)
+        );
+        let page = crate::render_page(key, &style_tag, &content, &sidebar, &linked_from, "");
+        let page = relativize_view_links(&page, depth);
+        let page = relativize_asset_links(&page, depth, root, outdir);
+
+        let out_path = outdir.join(key).with_extension("html");
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(out_path, page)?;
+    }
+
+    let home_href = files
+        .contains_key(home)
+        .then(|| home.to_string())
+        .or_else(|| files.keys().next().cloned())
+        .unwrap_or_default();
+    let home_href = home_href.strip_suffix(".md").unwrap_or(&home_href);
+    std::fs::write(
+        outdir.join("index.html"),
+        format!(r#"<!DOCTYPE html><meta http-equiv="refresh" content="0; url={home_href}.html">"#),
+    )?;
+
+    Ok(())
+}
+
+/// Rewrite `href="/view/<target>.md"` anchors (as produced by the sidebar,
+/// wikilinks, and backlinks sections) into relative `<target>.html` paths
+/// that work when the export is served as plain static files.
+fn relativize_view_links(html: &str, depth: usize) -> String {
+    let prefix = "../".repeat(depth);
+    rewrite_attr(html, "href=\"/view/", |target| {
+        let target = target.strip_suffix(".md").unwrap_or(target);
+        format!("href=\"{prefix}{target}.html\"")
+    })
+}
+
+/// Rewrite `/raw/<target>` asset references (images, attachment links) into
+/// relative paths and copy the referenced file to the same relative path
+/// under `outdir`.
+/// A failed copy is logged and otherwise skipped rather than aborting the
+/// whole export.
+fn relativize_asset_links(html: &str, depth: usize, root: &Path, outdir: &Path) -> String {
+    let prefix = "../".repeat(depth);
+    let html = rewrite_attr(html, "src=\"/raw/", |target| {
+        copy_asset(root, outdir, target);
+        format!("src=\"{prefix}{target}\"")
+    });
+    rewrite_attr(&html, "href=\"/raw/", |target| {
+        copy_asset(root, outdir, target);
+        format!("href=\"{prefix}{target}\"")
+    })
+}
+
+fn copy_asset(root: &Path, outdir: &Path, target: &str) {
+    let dst = outdir.join(target);
+    if dst.exists() {
+        return;
+    }
+    if let Some(parent) = dst.parent() {
+        if let Err(err) = std::fs::create_dir_all(parent) {
+            eprintln!("warning: failed to create directory for asset {target}: {err}");
+            return;
+        }
+    }
+    if let Err(err) = std::fs::copy(root.join(target), &dst) {
+        eprintln!("warning: failed to copy asset {target}: {err}");
+    }
+}
+
+/// Scan `html` for `needle="<target>"` attributes and replace each one with
+/// whatever `transform` returns for that target.
+fn rewrite_attr(html: &str, needle: &str, mut transform: impl FnMut(&str) -> String) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(pos) = rest.find(needle) {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos + needle.len()..];
+        let end = rest.find('"').unwrap_or(rest.len());
+        let target = &rest[..end];
+        out.push_str(&transform(target));
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrite_attr_replaces_every_match() {
+        let html = r#"<a href="/view/a.md">a</a> <a href="/view/b/c.md">c</a>"#;
+        let out = rewrite_attr(html, "href=\"/view/", |target| format!("href=\"{target}\""));
+        assert_eq!(
+            out,
+            r#"<a href="a.md">a</a> <a href="b/c.md">c</a>"#
+        );
+    }
+
+    #[test]
+    fn rewrite_attr_no_match_is_unchanged() {
+        let html = r#"<p>no links here</p>"#;
+        assert_eq!(rewrite_attr(html, "href=\"/view/", |t| t.to_string()), html);
+    }
+
+    #[test]
+    fn relativize_view_links_strips_md_and_adds_prefix() {
+        let html = r#"<a href="/view/notes/todo.md">todo</a>"#;
+        assert_eq!(
+            relativize_view_links(html, 1),
+            r#"<a href="../notes/todo.html">todo</a>"#
+        );
+    }
+
+    #[test]
+    fn relativize_view_links_root_depth() {
+        let html = r#"<a href="/view/index.md">index</a>"#;
+        assert_eq!(
+            relativize_view_links(html, 0),
+            r#"<a href="index.html">index</a>"#
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Wiki-style `[[links]]` resolution and backlink indexing.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Resolve a `[[target]]` against the known file keys, matching either the
+/// exact relative key (with or without a `.md` suffix) or a bare file stem.
+pub fn resolve(target: &str, files: &BTreeMap<String, String>) -> Option<String> {
+    if files.contains_key(target) {
+        return Some(target.to_string());
+    }
+    let with_ext = format!("{target}.md");
+    if files.contains_key(&with_ext) {
+        return Some(with_ext);
+    }
+    files
+        .keys()
+        .find(|key| {
+            Path::new(key)
+                .file_stem()
+                .is_some_and(|stem| stem == target)
+        })
+        .cloned()
+}
+
+/// Rewrite `[[target]]` tokens in raw markdown source into real anchors
+/// (resolved) or a "broken link" span (unresolved), ahead of markdown
+/// parsing. Raw HTML like this passes through pulldown-cmark untouched.
+pub fn render_wikilinks(source: &str, files: &BTreeMap<String, String>) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find("[[") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("]]") else {
+            out.push_str("[[");
+            break;
+        };
+        let label = &rest[..end];
+        rest = &rest[end + 2..];
+        match resolve(label, files) {
+            Some(target) => out.push_str(&format!(
+                r#"<a href="/view/{target}" class="wikilink">{label}</a>"#
+            )),
+            None => out.push_str(&format!(r#"<span class="wikilink broken">{label}</span>"#)),
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Resolved `[[link]]` targets referenced from `source`, deduplicated and
+/// skipping broken links.
+fn outgoing_links(source: &str, files: &BTreeMap<String, String>) -> BTreeSet<String> {
+    let mut targets = BTreeSet::new();
+    let mut rest = source;
+    while let Some(start) = rest.find("[[") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("]]") else {
+            break;
+        };
+        let label = &rest[..end];
+        rest = &rest[end + 2..];
+        if let Some(target) = resolve(label, files) {
+            targets.insert(target);
+        }
+    }
+    targets
+}
+
+/// Build an inverted index of backlinks: for every file, which *other* files
+/// link to it. A page linking to itself, or to the same target more than
+/// once, doesn't produce duplicate or self entries.
+pub fn build_backlinks(
+    root: &PathBuf,
+    files: &BTreeMap<String, String>,
+) -> BTreeMap<String, Vec<String>> {
+    let mut backlinks: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for key in files.keys() {
+        let Ok(source) = std::fs::read_to_string(root.join(key)) else {
+            continue;
+        };
+        for target in outgoing_links(&source, files) {
+            if target == *key {
+                continue;
+            }
+            backlinks.entry(target).or_default().push(key.clone());
+        }
+    }
+    backlinks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("notes/todo.md".to_string(), "todo".to_string()),
+            ("index.md".to_string(), "index".to_string()),
+        ])
+    }
+
+    #[test]
+    fn resolve_exact_key() {
+        assert_eq!(resolve("index.md", &files()), Some("index.md".to_string()));
+    }
+
+    #[test]
+    fn resolve_adds_md_suffix() {
+        assert_eq!(resolve("index", &files()), Some("index.md".to_string()));
+    }
+
+    #[test]
+    fn resolve_bare_stem() {
+        assert_eq!(
+            resolve("todo", &files()),
+            Some("notes/todo.md".to_string())
+        );
+    }
+
+    #[test]
+    fn resolve_unknown_target() {
+        assert_eq!(resolve("nope", &files()), None);
+    }
+
+    #[test]
+    fn render_wikilinks_resolved_and_broken() {
+        let out = render_wikilinks("See [[todo]] and [[nope]].", &files());
+        assert_eq!(
+            out,
+            r#"See <a href="/view/notes/todo.md" class="wikilink">todo</a> and <span class="wikilink broken">nope</span>."#
+        );
+    }
+
+    #[test]
+    fn build_backlinks_dedupes_and_skips_self() {
+        let dir = std::env::temp_dir().join(format!(
+            "displaymd-links-test-{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("index.md"), "[[todo]] [[todo]] [[index]]").unwrap();
+        std::fs::create_dir_all(dir.join("notes")).unwrap();
+        std::fs::write(dir.join("notes/todo.md"), "no links here").unwrap();
+
+        let backlinks = build_backlinks(&dir, &files());
+        assert_eq!(
+            backlinks.get("notes/todo.md"),
+            Some(&vec!["index.md".to_string()])
+        );
+        assert_eq!(backlinks.get("index.md"), None);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
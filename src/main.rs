@@ -1,12 +1,31 @@
 use axum::{
     Router,
+    body::Body,
     extract::{Path, State},
-    response::{Html, Redirect},
+    http::{StatusCode, header},
+    response::{
+        Html, IntoResponse, Redirect, Response,
+        sse::{Event as SseEvent, KeepAlive, Sse},
+    },
     routing::get,
 };
 use clap::Parser;
+use pulldown_cmark::{Options, Parser as MdParser, html};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
 use std::io;
-use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+use std::{path::PathBuf, sync::Arc};
+use tokio::sync::broadcast;
+use tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream};
+use tokio_util::io::ReaderStream;
+
+mod assets;
+mod cache;
+mod collect;
+mod export;
+mod links;
+mod sidebar;
+mod watch;
 
 #[derive(Parser)]
 #[command(name = "displaymd")]
@@ -22,24 +41,67 @@ struct Args {
     /// Home file to show at (relative path)
     #[arg(short = 'H', long, default_value = "README.md")]
     home: String,
+
+    /// Disable GitHub-flavored markdown extensions (tables, strikethrough, footnotes, tasklists)
+    #[arg(long)]
+    no_gfm: bool,
+
+    /// Render every page to static HTML under this directory instead of starting the server
+    #[arg(long, value_name = "DIR")]
+    export: Option<PathBuf>,
+
+    /// Comma-separated directory/file names or globs to exclude during collection
+    /// (dot-directories are always excluded)
+    #[arg(long, value_delimiter = ',')]
+    hidden: Vec<String>,
 }
 
 struct AppState {
     root: PathBuf,
+    gfm: bool,
+    hidden: Vec<String>,
+    pages: Arc<cache::PageCache>,
+    dirs: Arc<cache::DirCache>,
+    reload_tx: broadcast::Sender<String>,
+    // Kept alive for the life of the server; dropping it stops the watch.
+    _watcher: notify::RecommendedWatcher,
 }
 
 #[tokio::main]
 async fn main() {
     let args = Args::parse();
     let root = args.dir.canonicalize().expect("invalid path");
+    let gfm = !args.no_gfm;
+
+    if let Some(outdir) = &args.export {
+        export::run(&root, outdir, gfm, &args.home, &args.hidden).expect("export failed");
+        println!("Exported to {}", outdir.display());
+        return;
+    }
 
     println!("Serving: {}", root.display());
 
-    let state = Arc::new(AppState { root });
+    let pages = Arc::new(cache::PageCache::new());
+    let dirs = Arc::new(cache::DirCache::new());
+    let (reload_tx, _) = broadcast::channel(16);
+    let watcher = watch::spawn(root.clone(), pages.clone(), dirs.clone(), reload_tx.clone())
+        .expect("failed to start file watcher");
+
+    let state = Arc::new(AppState {
+        root,
+        gfm,
+        hidden: args.hidden,
+        pages,
+        dirs,
+        reload_tx,
+        _watcher: watcher,
+    });
 
     let app = Router::new()
         .route("/", get(index))
         .route("/view/{*path}", get(view))
+        .route("/raw/{*path}", get(raw))
+        .route("/__reload", get(reload))
         .with_state(state);
 
     let addr = format!("127.0.0.1:{}", args.port);
@@ -49,82 +111,149 @@ async fn main() {
 }
 
 async fn index(State(state): State<Arc<AppState>>) -> Redirect {
-    let files = collect_md_files(&state.root);
+    let (files, _) = state.dirs.get_or_collect(|| collect_files_and_backlinks(&state));
     let first = files.keys().next().cloned().unwrap_or_default();
     Redirect::to(&format!("/view/{first}"))
 }
 
+/// Walk the tree and build the backlink index together, so both stay in
+/// sync in `DirCache` and neither is recomputed more often than the other.
+fn collect_files_and_backlinks(
+    state: &AppState,
+) -> (BTreeMap<String, String>, BTreeMap<String, Vec<String>>) {
+    let files = collect::collect_md_files(&state.root, &state.hidden);
+    let backlinks = links::build_backlinks(&state.root, &files);
+    (files, backlinks)
+}
+
 // check a file exists in a subdir of root and
-fn file_to_markdown(root: &PathBuf, path: &String) -> io::Result<String> {
-    let file_path = match root.join(path).canonicalize() {
-        Ok(p) if p.starts_with(root) => p,
-        _ => return Err(io::ErrorKind::NotFound.into()),
+fn resolve_path(root: &PathBuf, path: &String) -> io::Result<PathBuf> {
+    match root.join(path).canonicalize() {
+        Ok(p) if p.starts_with(root) => Ok(p),
+        _ => Err(io::ErrorKind::NotFound.into()),
+    }
+}
+
+fn render_markdown(source: &str, gfm: bool, current: &str) -> String {
+    let mut options = Options::empty();
+    if gfm {
+        options.insert(Options::ENABLE_TABLES);
+        options.insert(Options::ENABLE_STRIKETHROUGH);
+        options.insert(Options::ENABLE_FOOTNOTES);
+        options.insert(Options::ENABLE_TASKLISTS);
+    }
+    let events: Vec<_> = MdParser::new_ext(source, options).collect();
+    let events = assets::rewrite_asset_links(events, current);
+    let mut html_out = String::new();
+    html::push_html(&mut html_out, events.into_iter());
+    html_out
+}
+
+/// Serve a file under `root` as-is (images, attachments, anything non-`.md`),
+/// guarded by the same canonicalization/traversal check as `view`.
+async fn raw(State(state): State<Arc<AppState>>, Path(path): Path<String>) -> Response {
+    let file_path = match resolve_path(&state.root, &path) {
+        Ok(p) => p,
+        Err(_) => return (StatusCode::NOT_FOUND, "Not found").into_response(),
+    };
+    let Ok(file) = tokio::fs::File::open(&file_path).await else {
+        return (StatusCode::NOT_FOUND, "Not found").into_response();
     };
-    std::fs::read_to_string(&file_path)
+    let mime = mime_guess::from_path(&file_path).first_or_octet_stream();
+    let body = Body::from_stream(ReaderStream::new(file));
+    ([(header::CONTENT_TYPE, mime.to_string())], body).into_response()
+}
+
+/// Server-Sent Events stream of changed file keys, consumed by the
+/// reload script injected into each served page.
+async fn reload(
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<SseEvent, Infallible>>> {
+    let stream = BroadcastStream::new(state.reload_tx.subscribe())
+        .filter_map(|msg| msg.ok().map(|key| Ok(SseEvent::default().data(key))));
+    Sse::new(stream).keep_alive(KeepAlive::default())
 }
 
 async fn view(State(state): State<Arc<AppState>>, Path(path): Path<String>) -> Html<String> {
-    let content = match file_to_markdown(&state.root, &path) {
-        Ok(c) => c,
-        _ => return Html("<p>File not found<p>".to_string()),
+    let file_path = match resolve_path(&state.root, &path) {
+        Ok(p) => p,
+        Err(_) => return Html("<p>File not found<p>".to_string()),
     };
-    let files = collect_md_files(&state.root);
-    let sidebar = build_sidebar(&files, &path);
+    let Ok(mtime) = std::fs::metadata(&file_path).and_then(|m| m.modified()) else {
+        return Html("<p>File not found<p>".to_string());
+    };
+    let (files, backlinks) = state.dirs.get_or_collect(|| collect_files_and_backlinks(&state));
+    if !files.contains_key(&path) {
+        return Html("<p>File not found<p>".to_string());
+    }
+    let content = state.pages.get_or_render(&path, mtime, || {
+        let raw = std::fs::read_to_string(&file_path).unwrap_or_default();
+        let body = collect::strip_front_matter(&raw);
+        let linked = links::render_wikilinks(body, &files);
+        render_markdown(&linked, state.gfm, &path)
+    });
+    let sidebar = sidebar::build_sidebar(&files, &path);
+    let linked_from = build_backlinks_section(backlinks.get(&path));
+    let style_tag = format!("<style>{CSS}</style>");
+    let script_tag = format!(
+        r#"<script>
+(function() {{
+    const page = "{path}";
+    const source = new EventSource("/__reload");
+    source.onmessage = (event) => {{
+        if (event.data === page) location.reload();
+    }};
+}})();
+</script>"#
+    );
 
-    Html(format!(
+    Html(render_page(
+        &path,
+        &style_tag,
+        &content,
+        &sidebar,
+        &linked_from,
+        &script_tag,
+    ))
+}
+
+fn render_page(
+    path: &str,
+    style_tag: &str,
+    content: &str,
+    sidebar: &str,
+    linked_from: &str,
+    script_tag: &str,
+) -> String {
+    format!(
         r#"<!DOCTYPE html>
 <html>
 <head>
     <meta charset="utf-8">
     <title>{path}</title>
-    <style>{CSS}</style>
+    {style_tag}
 </head>
 <body>
     <nav class="sidebar">{sidebar}</nav>
-    <main class="content">{content}</main>
+    <main class="content">
+        {content}
+        {linked_from}
+    </main>
+    {script_tag}
 </body>
 </html>"#
-    ))
+    )
 }
 
-fn collect_md_files(root: &PathBuf) -> BTreeMap<String, String> {
-    let mut files = BTreeMap::new();
-    collect_recursive(root, root, &mut files);
-    files
-}
-
-fn collect_recursive(root: &PathBuf, dir: &PathBuf, files: &mut BTreeMap<String, String>) {
-    let Ok(entries) = std::fs::read_dir(dir) else {
-        return;
+fn build_backlinks_section(linked_from: Option<&Vec<String>>) -> String {
+    let Some(linked_from) = linked_from.filter(|l| !l.is_empty()) else {
+        return String::new();
     };
-
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() {
-            collect_recursive(root, &path, files);
-        } else if path.extension().is_some_and(|e| e == "md") {
-            let relative = path.strip_prefix(root).unwrap();
-            let key = relative.to_string_lossy().to_string();
-            let name = path.file_stem().unwrap().to_string_lossy().to_string();
-            files.insert(key, name);
-        }
-    }
-}
-
-fn build_sidebar(files: &BTreeMap<String, String>, current: &str) -> String {
-    let mut out = String::from("<ul>");
-    for (path, name) in files {
-        let class = if path == current {
-            " class=\"active\""
-        } else {
-            ""
-        };
-        out.push_str(&format!(
-            r#"<li{class}><a href="/view/{path}">{name}</a></li>"#
-        ));
-    }
-    out.push_str("</ul>");
-    out
+    let items: String = linked_from
+        .iter()
+        .map(|path| format!(r#"<li><a href="/view/{path}">{path}</a></li>"#))
+        .collect();
+    format!(r#"<section class="backlinks"><h2>Linked from</h2><ul>{items}</ul></section>"#)
 }
 
 const CSS: &str = include_str!("../static/style.css");
@@ -0,0 +1,63 @@
+//! Nested folder tree for the sidebar navigation.
+
+use std::collections::BTreeMap;
+
+#[derive(Default)]
+struct Tree {
+    dirs: BTreeMap<String, Tree>,
+    files: Vec<(String, String)>,
+}
+
+impl Tree {
+    fn insert(&mut self, components: &[&str], key: &str, name: &str) {
+        match components {
+            [] => {}
+            [_last] => self.files.push((key.to_string(), name.to_string())),
+            [head, rest @ ..] => {
+                self.dirs
+                    .entry((*head).to_string())
+                    .or_default()
+                    .insert(rest, key, name);
+            }
+        }
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.files.iter().any(|(k, _)| k == key) || self.dirs.values().any(|d| d.contains(key))
+    }
+}
+
+fn build_tree(files: &BTreeMap<String, String>) -> Tree {
+    let mut root = Tree::default();
+    for (key, name) in files {
+        let components: Vec<&str> = key.split('/').collect();
+        root.insert(&components, key, name);
+    }
+    root
+}
+
+/// Render the sidebar as a recursive `<ul>` tree mirroring the directory
+/// structure. Each directory is a `<details>` element, auto-expanded when
+/// it contains `current`; the current page gets the `active` class.
+pub fn build_sidebar(files: &BTreeMap<String, String>, current: &str) -> String {
+    render(&build_tree(files), current)
+}
+
+fn render(tree: &Tree, current: &str) -> String {
+    let mut out = String::from("<ul>");
+    for (dir_name, subtree) in &tree.dirs {
+        let open_attr = if subtree.contains(current) { " open" } else { "" };
+        out.push_str(&format!(
+            r#"<li><details{open_attr}><summary>{dir_name}</summary>{}</details></li>"#,
+            render(subtree, current)
+        ));
+    }
+    for (key, name) in &tree.files {
+        let class = if key == current { " class=\"active\"" } else { "" };
+        out.push_str(&format!(
+            r#"<li{class}><a href="/view/{key}">{name}</a></li>"#
+        ));
+    }
+    out.push_str("</ul>");
+    out
+}
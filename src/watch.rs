@@ -0,0 +1,40 @@
+//! Watches `root` for changes, invalidating the page/dir caches and
+//! broadcasting the changed file's relative key for live reload.
+
+use crate::cache::{DirCache, PageCache};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+pub fn spawn(
+    root: PathBuf,
+    pages: Arc<PageCache>,
+    dirs: Arc<DirCache>,
+    tx: broadcast::Sender<String>,
+) -> notify::Result<RecommendedWatcher> {
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else { return };
+        let adds_or_removes_a_file = event.kind.is_create() || event.kind.is_remove();
+        if !(event.kind.is_modify() || adds_or_removes_a_file) {
+            return;
+        }
+        dirs.invalidate();
+        // A new or deleted file can change how *other* pages' `[[wikilinks]]`
+        // resolve and what their backlinks are, so a plain per-key
+        // invalidation isn't enough here.
+        if adds_or_removes_a_file {
+            pages.invalidate_all();
+        }
+        for path in &event.paths {
+            let Ok(relative) = path.strip_prefix(&root) else {
+                continue;
+            };
+            let key = relative.to_string_lossy().to_string();
+            pages.invalidate(&key);
+            let _ = tx.send(key);
+        }
+    })?;
+    watcher.watch(&root, RecursiveMode::Recursive)?;
+    Ok(watcher)
+}